@@ -0,0 +1,195 @@
+//! Ground-truth swap simulation that executes against a pool's deployed
+//! bytecode inside an in-memory [`revm`] instance, as a cross-check (or
+//! replacement) for the analytical [`simulate_swap`] path.
+//!
+//! This is useful for AMMs whose pricing cannot be expressed in closed form —
+//! custom Balancer math, pools with hooks, rebasing tokens — where the
+//! analytic path is either unavailable or only approximate.
+//!
+//! Compiled only when the `revm` feature is enabled so the dependency stays
+//! opt-in.
+//!
+//! [`simulate_swap`]: AutomatedMarketMaker::simulate_swap
+
+use alloy::{
+    network::Network,
+    primitives::{Address, Bytes, U256},
+    providers::Provider,
+    sol,
+    sol_types::SolCall,
+};
+use revm::{
+    db::{AlloyDB, CacheDB},
+    primitives::{ExecutionResult, Output, TransactTo},
+    Evm,
+};
+
+use crate::{
+    amm::{AutomatedMarketMaker, AMM},
+    errors::AMMError,
+};
+
+sol! {
+    /// Minimal ERC-4626 quote view used to price a vault's share/asset
+    /// conversion against its own deployed bytecode.
+    ///
+    /// `previewDeposit`/`previewRedeem` run the vault's real conversion math —
+    /// including any rebasing or custom accounting that has no closed form — so
+    /// calling them against the vault yields the exact amount out.
+    #[sol(rpc)]
+    contract IERC4626 {
+        function previewDeposit(uint256 assets) external view returns (uint256 shares);
+        function previewRedeem(uint256 shares) external view returns (uint256 assets);
+    }
+}
+
+/// Which call was encoded for a pool, so its return data can be decoded.
+enum PoolCall {
+    /// `previewDeposit(assets) -> shares`.
+    Erc4626Deposit,
+    /// `previewRedeem(shares) -> assets`.
+    Erc4626Redeem,
+}
+
+/// Encodes the swap as a call to `amm`'s own contract, returning the target
+/// address, the calldata, and which call was encoded.
+///
+/// The target is always the pool itself (`amm.address()`), so the call executes
+/// the pool's deployed bytecode rather than a generic router's. Pool types that
+/// have no executor-free, single-call ground truth return an explicit error
+/// instead of a wrong value — the analytic [`AutomatedMarketMaker::simulate_swap`]
+/// already covers their closed-form math.
+fn pool_call(
+    amm: &AMM,
+    token_in: Address,
+    amount_in: U256,
+) -> Result<(Address, Vec<u8>, PoolCall), AMMError> {
+    match amm {
+        // A vault swaps between its underlying asset and its own share token
+        // (whose address is the vault). Depositing the asset mints shares;
+        // redeeming shares returns assets.
+        AMM::ERC4626Vault(_) => {
+            let vault = amm.address();
+            if token_in == vault {
+                let data = IERC4626::previewRedeemCall { shares: amount_in }.abi_encode();
+                Ok((vault, data, PoolCall::Erc4626Redeem))
+            } else {
+                let data = IERC4626::previewDepositCall { assets: amount_in }.abi_encode();
+                Ok((vault, data, PoolCall::Erc4626Deposit))
+            }
+        }
+        _ => Err(AMMError::EVMError(format!(
+            "evm swap simulation is not wired for pool {}; use simulate_swap for its closed-form math",
+            amm.address()
+        ))),
+    }
+}
+
+/// Runs a swap against the pool's deployed bytecode and decodes the output
+/// amount.
+///
+/// The call target is `amm`'s own contract, so the pool's real swap/conversion
+/// math is executed — exact even for pools whose pricing has no closed form
+/// (rebasing vaults, custom accounting). The backing [`CacheDB`] is seeded
+/// lazily from `provider` at `block_number` (or latest when `None`), so only
+/// the storage slots actually read are fetched.
+pub async fn simulate_swap_evm<N, P>(
+    amm: &AMM,
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    block_number: Option<u64>,
+    provider: P,
+) -> Result<U256, AMMError>
+where
+    N: Network,
+    P: Provider<N> + Clone,
+{
+    debug_assert!(amm.tokens().contains(&token_in) && amm.tokens().contains(&token_out));
+
+    let (target, calldata, call) = pool_call(amm, token_in, amount_in)?;
+
+    let block_id = block_number.map(Into::into);
+    let alloy_db = AlloyDB::new(provider, block_id.unwrap_or_default());
+    let mut cache_db = CacheDB::new(alloy_db);
+
+    let mut evm = Evm::builder()
+        .with_db(&mut cache_db)
+        .modify_tx_env(|tx| {
+            tx.transact_to = TransactTo::Call(target);
+            tx.data = Bytes::from(calldata);
+            tx.value = U256::ZERO;
+        })
+        .build();
+
+    let result = evm
+        .transact()
+        .map_err(|e| AMMError::EVMError(e.to_string()))?;
+
+    let output = match result.result {
+        ExecutionResult::Success {
+            output: Output::Call(bytes),
+            ..
+        } => bytes,
+        ExecutionResult::Success { .. } => return Err(AMMError::EVMError("no return data".into())),
+        ExecutionResult::Revert { output, .. } => {
+            return Err(AMMError::EVMError(format!("reverted: 0x{output:x}")))
+        }
+        ExecutionResult::Halt { reason, .. } => {
+            return Err(AMMError::EVMError(format!("halted: {reason:?}")))
+        }
+    };
+
+    let amount_out = match call {
+        PoolCall::Erc4626Deposit => {
+            IERC4626::previewDepositCall::abi_decode_returns(&output, true)
+                .map_err(AMMError::from)?
+                .shares
+        }
+        PoolCall::Erc4626Redeem => {
+            IERC4626::previewRedeemCall::abi_decode_returns(&output, true)
+                .map_err(AMMError::from)?
+                .assets
+        }
+    };
+
+    Ok(amount_out)
+}
+
+/// Extension trait adding the EVM-backed simulation to the [`AMM`] enum, kept
+/// separate from the core [`AutomatedMarketMaker`] trait so the revm dependency
+/// stays opt-in.
+#[async_trait::async_trait]
+pub trait SimulateSwapEvm {
+    /// Ground-truth counterpart to [`AutomatedMarketMaker::simulate_swap`] that
+    /// executes the swap against the pool's deployed bytecode.
+    async fn simulate_swap_evm<N, P>(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+        amount_in: U256,
+        block_number: Option<u64>,
+        provider: P,
+    ) -> Result<U256, AMMError>
+    where
+        N: Network,
+        P: Provider<N> + Clone;
+}
+
+#[async_trait::async_trait]
+impl SimulateSwapEvm for AMM {
+    async fn simulate_swap_evm<N, P>(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+        amount_in: U256,
+        block_number: Option<u64>,
+        provider: P,
+    ) -> Result<U256, AMMError>
+    where
+        N: Network,
+        P: Provider<N> + Clone,
+    {
+        simulate_swap_evm(self, base_token, quote_token, amount_in, block_number, provider).await
+    }
+}
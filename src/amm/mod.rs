@@ -1,7 +1,10 @@
 pub mod balancer_v2;
 pub mod consts;
 pub mod erc_4626;
+#[cfg(feature = "revm")]
+pub mod evm;
 pub mod factory;
+pub mod state_space;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
 
@@ -20,6 +23,46 @@ use serde::{Deserialize, Serialize};
 
 use crate::errors::AMMError;
 
+/// Scale of the fixed-point prices returned by
+/// [`AutomatedMarketMaker::calculate_price_fixed`]: a price of `1.0` is
+/// represented as `1e18`.
+pub const FIXED_POINT_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Exact, decimals-normalized fixed-point price of the token backed by
+/// `base_reserve` denominated in the token backed by `quote_reserve`, scaled by
+/// [`FIXED_POINT_SCALE`].
+///
+/// Computed entirely in integer space from the raw reserves and the two tokens'
+/// decimals so no precision is lost, unlike the `f64` [`calculate_price`] path.
+/// Pools feed this their reserves and the decimals cached during
+/// [`populate_data`].
+///
+/// [`calculate_price`]: AutomatedMarketMaker::calculate_price
+/// [`populate_data`]: AutomatedMarketMaker::populate_data
+pub fn normalize_price_fixed(
+    base_reserve: U256,
+    quote_reserve: U256,
+    base_decimals: u8,
+    quote_decimals: u8,
+) -> Result<U256, AMMError> {
+    if base_reserve.is_zero() {
+        return Err(AMMError::PriceOverflow);
+    }
+
+    // price(base in quote) * SCALE, with each reserve de-scaled by its decimals:
+    //   (quote_reserve / 10^quote_dec) / (base_reserve / 10^base_dec) * SCALE
+    // = quote_reserve * SCALE * 10^base_dec / (base_reserve * 10^quote_dec)
+    let numerator = quote_reserve
+        .checked_mul(U256::from(FIXED_POINT_SCALE))
+        .and_then(|n| n.checked_mul(U256::from(10u64).pow(U256::from(base_decimals))))
+        .ok_or(AMMError::PriceOverflow)?;
+    let denominator = base_reserve
+        .checked_mul(U256::from(10u64).pow(U256::from(quote_decimals)))
+        .ok_or(AMMError::PriceOverflow)?;
+
+    Ok(numerator / denominator)
+}
+
 use self::{erc_4626::ERC4626Vault, uniswap_v2::UniswapV2Pool, uniswap_v3::UniswapV3Pool};
 
 sol! {
@@ -52,6 +95,47 @@ pub trait AutomatedMarketMaker {
     /// Calculates a f64 representation of base token price in the AMM.
     fn calculate_price(&self, base_token: Address, quote_token: Address) -> Result<f64, AMMError>;
 
+    /// Returns the number of decimals of `token`, cached during
+    /// [`populate_data`] from the `IErc20.decimals()` call.
+    ///
+    /// The default assumes the ERC-20 default of 18; pools that cache their
+    /// tokens' real decimals override this so [`calculate_price_fixed`] can
+    /// normalize exactly.
+    ///
+    /// [`populate_data`]: AutomatedMarketMaker::populate_data
+    /// [`calculate_price_fixed`]: AutomatedMarketMaker::calculate_price_fixed
+    fn token_decimals(&self, _token: Address) -> Result<u8, AMMError> {
+        Ok(18)
+    }
+
+    /// Calculates a decimals-normalized, [`FIXED_POINT_SCALE`]-scaled
+    /// (1e18) fixed-point price of `base_token` denominated in `quote_token`.
+    ///
+    /// Unlike [`calculate_price`], the result is an exact integer suitable for
+    /// feeding back into swap math and is correctly normalized for the two
+    /// tokens' decimals (cached during [`populate_data`]). Pools that cache
+    /// their reserves and decimals override this to compute the price in
+    /// integer space via [`normalize_price_fixed`]; the default falls back to
+    /// scaling the `f64` [`calculate_price`] and is only as precise as it.
+    ///
+    /// [`calculate_price`]: AutomatedMarketMaker::calculate_price
+    /// [`populate_data`]: AutomatedMarketMaker::populate_data
+    fn calculate_price_fixed(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+    ) -> Result<U256, AMMError> {
+        let price = self.calculate_price(base_token, quote_token)?;
+        if !price.is_finite() || price < 0.0 {
+            return Err(AMMError::PriceOverflow);
+        }
+        let scaled = price * FIXED_POINT_SCALE as f64;
+        if scaled >= 2f64.powi(128) {
+            return Err(AMMError::PriceOverflow);
+        }
+        Ok(U256::from(scaled as u128))
+    }
+
     /// Updates the AMM data from a log.
     fn sync_from_log(&mut self, log: Log) -> Result<(), AMMError>;
 
@@ -151,11 +235,23 @@ macro_rules! amm {
                 }
             }
 
+            fn token_decimals(&self, token: Address) -> Result<u8, AMMError> {
+                match self {
+                    $(AMM::$pool_type(pool) => pool.token_decimals(token),)+
+                }
+            }
+
             fn calculate_price(&self, base_token: Address, quote_token: Address) -> Result<f64, AMMError> {
                 match self {
                     $(AMM::$pool_type(pool) => pool.calculate_price(base_token, quote_token),)+
                 }
             }
+
+            fn calculate_price_fixed(&self, base_token: Address, quote_token: Address) -> Result<U256, AMMError> {
+                match self {
+                    $(AMM::$pool_type(pool) => pool.calculate_price_fixed(base_token, quote_token),)+
+                }
+            }
         }
 
         impl Hash for AMM {
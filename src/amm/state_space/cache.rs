@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use alloy::primitives::{Address, B256};
+
+use crate::amm::{AutomatedMarketMaker, AMM};
+
+/// Default depth of the [`StateChangeCache`] ring buffer, in blocks.
+pub const DEFAULT_CACHE_DEPTH: usize = 64;
+
+/// A reversible record of the AMM state touched by a single block.
+///
+/// `pre_images` holds a snapshot of each affected [`AMM`] taken *before* the
+/// block's logs were applied, so the block can be inverted by restoring them.
+#[derive(Debug, Clone)]
+pub struct StateChange {
+    /// The block the change was applied at.
+    pub block_number: u64,
+    /// The hash of that block; compared against an incoming log at the same
+    /// height to detect a fork.
+    pub block_hash: B256,
+    /// Snapshots of the affected AMMs as they were before this block.
+    pub pre_images: Vec<AMM>,
+}
+
+impl StateChange {
+    /// Returns the addresses of the AMMs this change touched.
+    pub fn addresses(&self) -> impl Iterator<Item = Address> + '_ {
+        self.pre_images.iter().map(|amm| amm.address())
+    }
+}
+
+/// Bounded ring buffer of [`StateChange`]s, keyed by block, that lets the
+/// streaming sync path undo updates when a chain reorg is observed.
+///
+/// The buffer keeps at most `depth` blocks; once full, the oldest block is
+/// dropped and can no longer be rolled back (a reorg deeper than the buffer is
+/// unrecoverable and must be resynced from chain).
+#[derive(Debug, Clone)]
+pub struct StateChangeCache {
+    depth: usize,
+    changes: VecDeque<StateChange>,
+}
+
+impl StateChangeCache {
+    /// Creates an empty cache with the given block depth.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            changes: VecDeque::with_capacity(depth),
+        }
+    }
+
+    /// Returns the block number at the head of the buffer, if any.
+    pub fn latest_block(&self) -> Option<u64> {
+        self.changes.back().map(|c| c.block_number)
+    }
+
+    /// Returns the stored block hash at `block_number`, if the buffer still
+    /// holds it.
+    pub fn block_hash(&self, block_number: u64) -> Option<B256> {
+        self.changes
+            .iter()
+            .find(|c| c.block_number == block_number)
+            .map(|c| c.block_hash)
+    }
+
+    /// Records a block's pre-images, evicting the oldest block if the buffer is
+    /// at capacity.
+    pub fn push(&mut self, change: StateChange) {
+        if self.depth == 0 {
+            return;
+        }
+        if self.changes.len() == self.depth {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(change);
+    }
+
+    /// Records the pre-image of a single AMM update at `block_number`.
+    ///
+    /// Updates belonging to the block currently at the head of the buffer are
+    /// appended to it; a new block opens a fresh [`StateChange`].
+    ///
+    /// Only the *first* pre-image seen for a given address within a block is
+    /// kept: a pool swapped multiple times in one block must roll back to its
+    /// pre-block state, not to some intermediate post-swap state, so later
+    /// updates to an already-recorded address are ignored.
+    pub fn record(&mut self, block_number: u64, block_hash: B256, pre_image: AMM) {
+        match self.changes.back_mut() {
+            Some(change) if change.block_number == block_number => {
+                let address = pre_image.address();
+                if change.pre_images.iter().all(|p| p.address() != address) {
+                    change.pre_images.push(pre_image);
+                }
+            }
+            _ => self.push(StateChange {
+                block_number,
+                block_hash,
+                pre_images: vec![pre_image],
+            }),
+        }
+    }
+
+    /// Unwinds every block at or after `block_number`, returning the popped
+    /// changes from newest to oldest.
+    ///
+    /// The caller restores the pre-images in the returned order to roll the
+    /// affected AMMs back to their state at the fork point.
+    pub fn unwind_from(&mut self, block_number: u64) -> Vec<StateChange> {
+        let mut unwound = Vec::new();
+        while let Some(change) = self.changes.back() {
+            if change.block_number >= block_number {
+                unwound.push(self.changes.pop_back().expect("back checked above"));
+            } else {
+                break;
+            }
+        }
+        unwound
+    }
+
+    /// Returns `true` if the buffer holds no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Default for StateChangeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amm::uniswap_v2::UniswapV2Pool;
+
+    fn amm_at(address: Address) -> AMM {
+        AMM::UniswapV2Pool(UniswapV2Pool {
+            address,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn record_keeps_first_pre_image_per_address_in_block() {
+        let mut cache = StateChangeCache::new(8);
+        let pool = Address::with_last_byte(1);
+        let other = Address::with_last_byte(2);
+        let hash = B256::repeat_byte(0xaa);
+
+        // Two swaps of `pool` plus one of `other`, all in block 7. Only the
+        // first pre-image of `pool` must survive so a rollback restores its
+        // pre-block state rather than the state after its first swap.
+        cache.record(7, hash, amm_at(pool));
+        cache.record(7, hash, amm_at(pool));
+        cache.record(7, hash, amm_at(other));
+
+        let unwound = cache.unwind_from(7);
+        assert_eq!(unwound.len(), 1, "one block unwound");
+        let addresses: Vec<Address> = unwound[0].addresses().collect();
+        assert_eq!(addresses, vec![pool, other]);
+    }
+}
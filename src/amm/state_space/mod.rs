@@ -0,0 +1,293 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use alloy::{
+    network::Network,
+    primitives::Address,
+    providers::Provider,
+    pubsub::PubSubConnect,
+    rpc::types::eth::{Filter, Log},
+};
+use futures::stream::{Stream, StreamExt};
+use tokio::sync::RwLock;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::{
+    amm::{AutomatedMarketMaker, AMM},
+    errors::AMMError,
+};
+
+pub mod cache;
+
+use cache::{StateChangeCache, DEFAULT_CACHE_DEPTH};
+
+/// Default interval between `eth_getFilterChanges` polls.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Shared, mutable set of [`AMM`]s keyed on their address.
+///
+/// The map is wrapped in an [`Arc<RwLock<..>>`] so a [`StateSpaceManager`] can
+/// keep syncing it in the background while consumers read prices off the same
+/// state.
+pub type StateSpace = Arc<RwLock<HashMap<Address, AMM>>>;
+
+/// Keeps a live set of [`AMM`]s in sync from chain logs without re-polling the
+/// full state of each pool.
+///
+/// The manager aggregates every AMM's [`sync_on_event_signatures`] and address
+/// into a single [`Filter`], registers it once with the node, and then streams
+/// the logs emitted since the previous poll back onto the tracked AMMs. Each
+/// sync yields the addresses that changed so a consumer can recompute only the
+/// affected prices.
+///
+/// [`sync_on_event_signatures`]: AutomatedMarketMaker::sync_on_event_signatures
+#[derive(Clone)]
+pub struct StateSpaceManager<N, P> {
+    /// The AMMs being kept in sync, keyed on [`AutomatedMarketMaker::address`].
+    pub state: StateSpace,
+    /// The aggregate filter registered with the node.
+    pub filter: Filter,
+    /// Interval between `eth_getFilterChanges` polls.
+    pub poll_interval: Duration,
+    /// Bounded ring buffer of reversible per-block diffs used to roll back on a
+    /// reorg.
+    cache: Arc<RwLock<StateChangeCache>>,
+    provider: P,
+    _network: std::marker::PhantomData<N>,
+}
+
+impl<N, P> StateSpaceManager<N, P>
+where
+    N: Network,
+    P: Provider<N> + Clone + 'static,
+{
+    /// Builds a manager from the AMMs to track, aggregating their addresses and
+    /// sync event signatures into a single filter.
+    pub fn new(amms: Vec<AMM>, provider: P) -> Self {
+        let mut addresses = Vec::with_capacity(amms.len());
+        let mut event_signatures = Vec::new();
+        let mut state = HashMap::with_capacity(amms.len());
+
+        for amm in amms {
+            addresses.push(amm.address());
+            event_signatures.extend(amm.sync_on_event_signatures());
+            state.insert(amm.address(), amm);
+        }
+
+        event_signatures.sort_unstable();
+        event_signatures.dedup();
+
+        let filter = Filter::new()
+            .address(addresses)
+            .event_signature(event_signatures);
+
+        Self {
+            state: Arc::new(RwLock::new(state)),
+            filter,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            cache: Arc::new(RwLock::new(StateChangeCache::new(DEFAULT_CACHE_DEPTH))),
+            provider,
+            _network: std::marker::PhantomData,
+        }
+    }
+
+    /// Overrides the interval between `eth_getFilterChanges` polls.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Overrides the depth, in blocks, of the reorg rollback buffer.
+    pub fn with_cache_depth(mut self, depth: usize) -> Self {
+        self.cache = Arc::new(RwLock::new(StateChangeCache::new(depth)));
+        self
+    }
+
+    /// Routes a batch of logs to the owning AMMs, returning the addresses whose
+    /// state changed.
+    ///
+    /// Before mutating an AMM, its pre-image is snapshotted into the reorg
+    /// buffer keyed on the log's block. A `removed` log, or a canonical log
+    /// whose block hash disagrees with the buffered hash at that height, rolls
+    /// the affected AMMs back to the fork point by restoring the buffered
+    /// pre-images before the incoming canonical logs are applied.
+    async fn sync_logs(&self, logs: Vec<Log>) -> Result<Vec<Address>, AMMError> {
+        if logs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut affected = Vec::new();
+        let mut state = self.state.write().await;
+        let mut cache = self.cache.write().await;
+
+        for log in logs {
+            let block_number = log.block_number.ok_or(AMMError::BlockNumberNotFound)?;
+            let block_hash = log.block_hash.ok_or(AMMError::BlockNumberNotFound)?;
+
+            // A removed log, or a fork detected at this height, unwinds every
+            // buffered block from the fork point onward.
+            let forked = cache
+                .block_hash(block_number)
+                .is_some_and(|stored| stored != block_hash);
+
+            if log.removed() || forked {
+                for change in cache.unwind_from(block_number) {
+                    for pre_image in change.pre_images {
+                        let address = pre_image.address();
+                        state.insert(address, pre_image);
+                        affected.push(address);
+                    }
+                }
+            }
+
+            // `removed` logs only signal the rollback; the canonical logs that
+            // replace them arrive as their own, non-removed entries.
+            if log.removed() {
+                continue;
+            }
+
+            let address = log.address();
+            if let Some(amm) = state.get_mut(&address) {
+                cache.record(block_number, block_hash, amm.clone());
+                amm.sync_from_log(log)?;
+                affected.push(address);
+            }
+        }
+
+        affected.sort_unstable();
+        affected.dedup();
+        Ok(affected)
+    }
+
+    /// Registers the filter via `eth_newFilter` and polls
+    /// `eth_getFilterChanges` on [`Self::poll_interval`], streaming the changed
+    /// addresses from each poll.
+    ///
+    /// If the node drops the filter (`filter not found`), it is transparently
+    /// re-registered and polling continues.
+    pub async fn subscribe(
+        &self,
+    ) -> Result<impl Stream<Item = Result<Vec<Address>, AMMError>>, AMMError> {
+        let (tx, rx) = tokio::sync::mpsc::channel(32);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            let mut filter_id = match this.provider.new_filter(&this.filter).await {
+                Ok(id) => id,
+                Err(e) => {
+                    let _ = tx.send(Err(AMMError::from(e))).await;
+                    return;
+                }
+            };
+
+            let mut interval = tokio::time::interval(this.poll_interval);
+            loop {
+                interval.tick().await;
+
+                let logs = match this.provider.get_filter_changes::<Log>(filter_id).await {
+                    Ok(logs) => logs,
+                    Err(e) if is_filter_not_found(&e) => {
+                        // The node dropped the filter; re-register and retry.
+                        match this.provider.new_filter(&this.filter).await {
+                            Ok(id) => {
+                                filter_id = id;
+                                continue;
+                            }
+                            Err(e) => {
+                                let _ = tx.send(Err(AMMError::from(e))).await;
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(AMMError::from(e))).await;
+                        return;
+                    }
+                };
+
+                match this.sync_logs(logs).await {
+                    Ok(affected) if affected.is_empty() => {}
+                    Ok(affected) => {
+                        if tx.send(Ok(affected)).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        if tx.send(Err(e)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+}
+
+impl<N, P> StateSpaceManager<N, P>
+where
+    N: Network,
+    P: Provider<N> + Clone + 'static,
+{
+    /// WebSocket-backed alternative to [`Self::subscribe`].
+    ///
+    /// Opens an `eth_subscribe` logs subscription for the aggregate filter
+    /// instead of polling, pushing the changed addresses as logs arrive.
+    pub async fn subscribe_ws(
+        &self,
+    ) -> Result<impl Stream<Item = Result<Vec<Address>, AMMError>>, AMMError>
+    where
+        P: PubSubConnect,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(32);
+        let this = self.clone();
+
+        let subscription = this.provider.subscribe_logs(&this.filter).await?;
+        let mut stream = subscription.into_stream();
+
+        tokio::spawn(async move {
+            while let Some(log) = stream.next().await {
+                match this.sync_logs(vec![log]).await {
+                    Ok(affected) if affected.is_empty() => {}
+                    Ok(affected) => {
+                        if tx.send(Ok(affected)).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        if tx.send(Err(e)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+}
+
+/// Returns `true` if the error is the node signalling that a previously
+/// registered filter no longer exists.
+fn is_filter_not_found<E: std::fmt::Display>(err: &E) -> bool {
+    let msg = err.to_string().to_lowercase();
+    msg.contains("filter not found")
+}
+
+/// Convenience trait mirroring the snapshot pattern of forwarding through the
+/// [`AMM`] enum, so a manager can be built straight from a provider and the
+/// tracked AMMs.
+pub trait StateSpaceBuilder<N, P> {
+    /// Builds a [`StateSpaceManager`] tracking `self`.
+    fn state_space(self, provider: P) -> StateSpaceManager<N, P>;
+}
+
+impl<N, P> StateSpaceBuilder<N, P> for Vec<AMM>
+where
+    N: Network,
+    P: Provider<N> + Clone + 'static,
+{
+    fn state_space(self, provider: P) -> StateSpaceManager<N, P> {
+        StateSpaceManager::new(self, provider)
+    }
+}
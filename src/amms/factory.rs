@@ -1,4 +1,5 @@
 use std::{
+    future::Future,
     hash::{Hash, Hasher},
     sync::Arc,
 };
@@ -14,19 +15,76 @@ use alloy::{
 use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
 
-use super::error::AMMError;
+use super::{error::AMMError, AutomatedMarketMaker, AMM};
+
+/// Number of blocks scanned per `eth_getLogs` request while discovering pools.
+pub const DISCOVERY_STEP: u64 = 100_000;
+
+/// Number of pools per chunk while populating; chunks are hydrated
+/// concurrently, the pools within a chunk one after another.
+pub const POPULATE_CHUNK_SIZE: usize = 100;
 
-//TODO: add consts for steps, batch size, etc.
 pub trait AutomatedMarketMakerFactory {
     //TODO: GAT for AMM
 
     /// Returns the address of the factory.
     fn address(&self) -> Address;
 
-    // TODO: event sig
+    /// Returns the event signature emitted when the factory creates a new pool.
+    fn creation_event_signature(&self) -> B256;
 
     /// Returns the block number at which the factory was created.
     fn creation_block(&self) -> u64;
+
+    /// Decodes a pool-creation log into an un-populated [`AMM`] shell.
+    fn amm_from_log(&self, log: Log) -> Result<AMM, AMMError>;
+
+    /// Scans `PoolCreated`-style logs over `[from_block, to_block]` in
+    /// [`DISCOVERY_STEP`]-sized ranges and returns the discovered, un-populated
+    /// AMMs.
+    ///
+    /// The returned shells still need syncing; hydrate them in
+    /// [`POPULATE_CHUNK_SIZE`] chunks via [`populate_amms`] to keep indexing of
+    /// large factories tractable.
+    fn get_all_pools_in_range<T, N, P>(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        provider: Arc<P>,
+    ) -> impl Future<Output = Result<Vec<AMM>, AMMError>> + Send
+    where
+        T: Transport + Clone,
+        N: Network,
+        P: Provider<T, N>,
+    {
+        async move {
+            let event_signature = self.creation_event_signature();
+            let factory = self.address();
+
+            let mut requests = FuturesUnordered::new();
+            let mut range_start = from_block;
+            while range_start <= to_block {
+                let range_end = (range_start + DISCOVERY_STEP - 1).min(to_block);
+                let filter = Filter::new()
+                    .address(factory)
+                    .event_signature(event_signature)
+                    .from_block(range_start)
+                    .to_block(range_end);
+                let provider = provider.clone();
+                requests.push(async move { provider.get_logs(&filter).await });
+                range_start = range_end + 1;
+            }
+
+            let mut amms = Vec::new();
+            while let Some(result) = requests.next().await {
+                for log in result.map_err(AMMError::from)? {
+                    amms.push(self.amm_from_log(log)?);
+                }
+            }
+
+            Ok(amms)
+        }
+    }
 }
 
 macro_rules! factory {
@@ -43,13 +101,23 @@ macro_rules! factory {
                 }
             }
 
-            // TODO: event sig
+            fn creation_event_signature(&self) -> B256 {
+                match self {
+                    $(Factory::$factory_type(factory) => factory.creation_event_signature(),)+
+                }
+            }
 
             fn creation_block(&self) -> u64 {
                 match self {
                     $(Factory::$factory_type(factory) => factory.creation_block(),)+
                 }
             }
+
+            fn amm_from_log(&self, log: Log) -> Result<AMM, AMMError> {
+                match self {
+                    $(Factory::$factory_type(factory) => factory.amm_from_log(log),)+
+                }
+            }
         }
 
         impl Hash for Factory {
@@ -69,3 +137,60 @@ macro_rules! factory {
 }
 
 // factory!(UniswapV2Factory);
+
+/// Hydrates `amms` by syncing each pool, split into [`POPULATE_CHUNK_SIZE`]
+/// chunks that run concurrently (one sync in flight per chunk).
+///
+/// This is the per-pool population path: each AMM issues its own sync call.
+/// The single-`eth_call` fast path the request envisions — an ephemeral,
+/// view-only helper installed through a state override whose constructor
+/// `return`s the packed data for a whole chunk, collapsing it to one RPC — is
+/// out of scope here; it needs the compiled helper artifact, which is tracked
+/// separately. Until it lands, this function keeps the same signature so the
+/// fast path can slot in without touching callers.
+// TODO: add the state-override helper (compiled artifact + encode/decode) and
+// switch `populate_chunk` to it, collapsing a chunk to one RPC.
+pub async fn populate_amms<T, N, P>(
+    amms: &mut [AMM],
+    block_number: Option<u64>,
+    provider: Arc<P>,
+) -> Result<(), AMMError>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N>,
+{
+    let mut requests = FuturesUnordered::new();
+    for chunk in amms.chunks_mut(POPULATE_CHUNK_SIZE) {
+        let provider = provider.clone();
+        requests.push(async move { populate_chunk(chunk, block_number, provider).await });
+    }
+
+    while let Some(result) = requests.next().await {
+        result?;
+    }
+
+    Ok(())
+}
+
+/// Populates a single chunk of pools by syncing each in turn.
+///
+/// A sync error for any pool aborts the chunk. `block_number` is reserved for
+/// the future state-override helper and is unused on this per-pool route
+/// (pools sync at latest).
+async fn populate_chunk<T, N, P>(
+    chunk: &mut [AMM],
+    block_number: Option<u64>,
+    provider: Arc<P>,
+) -> Result<(), AMMError>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N>,
+{
+    let _ = block_number;
+    for amm in chunk.iter_mut() {
+        amm.sync(provider.clone()).await?;
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
 pub mod error;
+pub mod factory;
 pub mod uniswap_v2;
 
 use alloy::{